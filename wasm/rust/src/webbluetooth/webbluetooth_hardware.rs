@@ -21,13 +21,16 @@ use buttplug::{
   },
   util::future::{ButtplugFuture, ButtplugFutureStateShared},
 };
-use futures::future::{self, BoxFuture};
+use futures::future::{self, BoxFuture, Either};
+use gloo_timers::future::TimeoutFuture;
 use js_sys::{DataView, Uint8Array};
 use std::{
-  collections::HashMap,
+  collections::{HashMap, HashSet},
   convert::TryFrom,
   fmt::{self, Debug},
+  sync::Arc,
 };
+use tokio::select;
 use tokio::sync::{broadcast, mpsc};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
@@ -41,8 +44,46 @@ use web_sys::{
   MessageEvent,
 };
 
+use super::webbluetooth_manager::{GattAccess, GattBlocklist};
+use uuid::Uuid;
+
 type WebBluetoothResultFuture = ButtplugFuture<Result<(), ButtplugDeviceError>>;
 type WebBluetoothReadResultFuture = ButtplugFuture<Result<HardwareReading, ButtplugDeviceError>>;
+type WebBluetoothBatteryResultFuture = ButtplugFuture<Result<u8, ButtplugDeviceError>>;
+
+// Per the Bluetooth core spec, a GATT transaction that hasn't completed within
+// 30 seconds should be treated as failed rather than left pending forever.
+const DEFAULT_GATT_TIMEOUT_MS: u32 = 30_000;
+
+// Backoff schedule for reconnect attempts after a `gattserverdisconnected`
+// event: 1s, 2s, 4s, ... up to this cap.
+const RECONNECT_INITIAL_BACKOFF_MS: u32 = 1_000;
+const RECONNECT_MAX_BACKOFF_MS: u32 = 16_000;
+pub(crate) const DEFAULT_MAX_RECONNECT_ATTEMPTS: u32 = 5;
+
+// Standard GATT Battery Service / Battery Level characteristic, present on
+// most toys regardless of protocol, so we look for it independently of the
+// per-protocol service table.
+const BATTERY_SERVICE_UUID: &str = "0000180f-0000-1000-8000-00805f9b34fb";
+const BATTERY_LEVEL_CHARACTERISTIC_UUID: &str = "00002a19-0000-1000-8000-00805f9b34fb";
+
+// `tokio::time::timeout` isn't available under wasm, so we race the GATT
+// promise against a `setTimeout`-backed timer future instead.
+async fn await_gatt_operation(
+  fut: JsFuture,
+  timeout_ms: u32,
+) -> Result<JsValue, ButtplugDeviceError> {
+  match future::select(fut, TimeoutFuture::new(timeout_ms)).await {
+    Either::Left((Ok(value), _)) => Ok(value),
+    Either::Left((Err(err), _)) => Err(ButtplugDeviceError::DeviceCommunicationError(format!(
+      "GATT operation failed: {:?}",
+      err
+    ))),
+    Either::Right((_, _)) => Err(ButtplugDeviceError::DeviceCommunicationError(
+      "GATT transaction timed out".to_string(),
+    )),
+  }
+}
 
 struct BluetoothDeviceWrapper {
   pub device: BluetoothDevice,
@@ -51,14 +92,43 @@ struct BluetoothDeviceWrapper {
 unsafe impl Send for BluetoothDeviceWrapper {}
 unsafe impl Sync for BluetoothDeviceWrapper {}
 
+// Knobs shared by every WebBluetooth device, gathered here so the manager can
+// build them once (e.g. the blocklist) and hand them down to each connection.
+#[derive(Clone)]
+pub struct WebBluetoothConfig {
+  pub gatt_timeout_ms: u32,
+  pub blocklist: Arc<GattBlocklist>,
+  // Whether a `gattserverdisconnected` event should trigger automatic
+  // reconnection instead of immediately surfacing `HardwareEvent::Disconnected`.
+  pub reconnect: bool,
+  pub max_reconnect_attempts: u32,
+}
+
+impl Default for WebBluetoothConfig {
+  fn default() -> Self {
+    Self {
+      gatt_timeout_ms: DEFAULT_GATT_TIMEOUT_MS,
+      blocklist: Arc::new(GattBlocklist::new()),
+      reconnect: false,
+      max_reconnect_attempts: DEFAULT_MAX_RECONNECT_ATTEMPTS,
+    }
+  }
+}
+
 pub struct WebBluetoothHardwareConnector {
   device: Option<BluetoothDeviceWrapper>,
+  config: WebBluetoothConfig,
 }
 
 impl WebBluetoothHardwareConnector {
   pub fn new(device: BluetoothDevice) -> Self {
+    Self::new_with_config(device, WebBluetoothConfig::default())
+  }
+
+  pub fn new_with_config(device: BluetoothDevice, config: WebBluetoothConfig) -> Self {
     Self {
       device: Some(BluetoothDeviceWrapper { device }),
+      config,
     }
   }
 }
@@ -84,18 +154,21 @@ impl HardwareConnector for WebBluetoothHardwareConnector {
   async fn connect(&mut self) -> Result<Box<dyn HardwareSpecializer>, ButtplugDeviceError> {
     Ok(Box::new(WebBluetoothHardwareSpecializer::new(
       self.device.take().unwrap(),
+      self.config.clone(),
     )))
   }
 }
 
 pub struct WebBluetoothHardwareSpecializer {
   device: Option<BluetoothDeviceWrapper>,
+  config: WebBluetoothConfig,
 }
 
 impl WebBluetoothHardwareSpecializer {
-  fn new(device: BluetoothDeviceWrapper) -> Self {
+  fn new(device: BluetoothDeviceWrapper, config: WebBluetoothConfig) -> Self {
     Self {
       device: Some(device),
+      config,
     }
   }
 }
@@ -128,6 +201,7 @@ impl HardwareSpecializer for WebBluetoothHardwareSpecializer {
         sender,
         event_sender.clone(),
         command_receiver,
+        self.config.clone(),
       );
       spawn_local(async move {
         event_loop_fut.await;
@@ -174,74 +248,281 @@ pub enum WebBluetoothDeviceCommand {
     HardwareUnsubscribeCmd,
     ButtplugFutureStateShared<Result<(), ButtplugDeviceError>>,
   ),
+  ReadBattery(ButtplugFutureStateShared<Result<u8, ButtplugDeviceError>>),
 }
 
-async fn run_webbluetooth_loop(
-  device: BluetoothDevice,
-  btle_protocol: BluetoothLESpecifier,
-  device_local_event_sender: mpsc::Sender<WebBluetoothEvent>,
-  device_external_event_sender: broadcast::Sender<HardwareEvent>,
-  mut device_command_receiver: mpsc::Receiver<WebBluetoothDeviceCommand>,
-) {
+// Connects to the GATT server and walks the configured services/endpoints to
+// build a fresh characteristic map. Used for both the initial connection and
+// every reconnect attempt.
+async fn discover_gatt(
+  device: &BluetoothDevice,
+  btle_protocol: &BluetoothLESpecifier,
+  blocklist: &GattBlocklist,
+  gatt_timeout_ms: u32,
+) -> Result<
+  (
+    HashMap<Endpoint, BluetoothRemoteGattCharacteristic>,
+    HashSet<Endpoint>,
+  ),
+  ButtplugDeviceError,
+> {
   let mut char_map = HashMap::new();
+  let mut write_blocked_endpoints = HashSet::new();
   let connect_future = device.gatt().unwrap().connect();
-  let server: BluetoothRemoteGattServer = match JsFuture::from(connect_future).await {
-    Ok(val) => val.into(),
-    Err(_) => {
-      device_local_event_sender
-        .send(WebBluetoothEvent::Disconnected)
-        .await
-        .unwrap();
-      return;
-    }
-  };
+  let server: BluetoothRemoteGattServer =
+    await_gatt_operation(JsFuture::from(connect_future), gatt_timeout_ms)
+      .await?
+      .into();
   for (service_uuid, service_endpoints) in btle_protocol.services() {
-    let service = if let Ok(serv) =
-      JsFuture::from(server.get_primary_service_with_str(&service_uuid.to_string())).await
-    {
-      info!(
-        "Service {} found on device {}",
-        service_uuid,
-        device.name().unwrap()
-      );
-      BluetoothRemoteGattService::from(serv)
-    } else {
-      info!(
-        "Service {} not found on device {}",
-        service_uuid,
-        device.name().unwrap()
-      );
+    if blocklist.uuid_is_blocklisted(service_uuid, GattAccess::Read) {
+      info!("Service {} is blocklisted, skipping", service_uuid);
       continue;
+    }
+    let service = match await_gatt_operation(
+      JsFuture::from(server.get_primary_service_with_str(&service_uuid.to_string())),
+      gatt_timeout_ms,
+    )
+    .await
+    {
+      Ok(serv) => {
+        info!(
+          "Service {} found on device {}",
+          service_uuid,
+          device.name().unwrap()
+        );
+        BluetoothRemoteGattService::from(serv)
+      }
+      Err(err) => {
+        info!(
+          "Service {} not found on device {}: {:?}",
+          service_uuid,
+          device.name().unwrap(),
+          err
+        );
+        continue;
+      }
     };
     for (chr_name, chr_uuid) in service_endpoints.iter() {
+      if blocklist.uuid_is_blocklisted(chr_uuid, GattAccess::Read) {
+        info!("Characteristic {} is blocklisted, skipping", chr_uuid);
+        continue;
+      }
       info!("Connecting chr {} {}", chr_name, chr_uuid.to_string());
-      let char: BluetoothRemoteGattCharacteristic =
-        JsFuture::from(service.get_characteristic_with_str(&chr_uuid.to_string()))
-          .await
-          .unwrap()
-          .into();
-      // Explicitly map Powerblow characteristics
-      match chr_uuid.to_string().as_str() {
-        "00001401-0000-1000-8000-00805f9b34fb" => {
-          char_map.insert(Endpoint::Tx, char); // Motor
-        }
-        "00001402-0000-1000-8000-00805f9b34fb" => {
-          char_map.insert(Endpoint::Rx, char); // Solenoid
-        }
-        _ => {
-          char_map.insert(chr_name.clone(), char); // Other characteristics
+      let char: BluetoothRemoteGattCharacteristic = match await_gatt_operation(
+        JsFuture::from(service.get_characteristic_with_str(&chr_uuid.to_string())),
+        gatt_timeout_ms,
+      )
+      .await
+      {
+        Ok(chr) => chr.into(),
+        Err(err) => {
+          info!(
+            "Characteristic {} not found on device {}: {:?}",
+            chr_uuid,
+            device.name().unwrap(),
+            err
+          );
+          continue;
         }
+      };
+      // Endpoint comes straight from the protocol config's service_endpoints
+      // table, so any protocol's BluetoothLESpecifier works unmodified.
+      let endpoint = chr_name.clone();
+      if blocklist.uuid_is_blocklisted(chr_uuid, GattAccess::Write) {
+        write_blocked_endpoints.insert(endpoint.clone());
       }
+      char_map.insert(endpoint, char);
+    }
+  }
+  if !char_map.contains_key(&Endpoint::RxBLEBattery) {
+    if let Some(battery_chr) = discover_battery_characteristic(device, &server, blocklist, gatt_timeout_ms).await {
+      char_map.insert(Endpoint::RxBLEBattery, battery_chr);
     }
   }
+  Ok((char_map, write_blocked_endpoints))
+}
+
+// Looks for the standard Battery Service (0x180F) / Battery Level (0x2A19)
+// characteristic. Absence is expected for most devices, so failures are
+// logged rather than propagated. Runs the same blocklist check as the
+// config-driven loop above, since `ReadBattery` reads this characteristic
+// and the loop auto-subscribes to it just like any other endpoint.
+async fn discover_battery_characteristic(
+  device: &BluetoothDevice,
+  server: &BluetoothRemoteGattServer,
+  blocklist: &GattBlocklist,
+  gatt_timeout_ms: u32,
+) -> Option<BluetoothRemoteGattCharacteristic> {
+  let battery_level_uuid = Uuid::parse_str(BATTERY_LEVEL_CHARACTERISTIC_UUID).unwrap();
+  if blocklist.uuid_is_blocklisted(&battery_level_uuid, GattAccess::Read)
+    || blocklist.uuid_is_blocklisted(&battery_level_uuid, GattAccess::Subscribe)
   {
+    info!("Battery Level characteristic is blocklisted, skipping");
+    return None;
+  }
+  let service = await_gatt_operation(
+    JsFuture::from(server.get_primary_service_with_str(BATTERY_SERVICE_UUID)),
+    gatt_timeout_ms,
+  )
+  .await
+  .ok()?;
+  let service = BluetoothRemoteGattService::from(service);
+  let chr = await_gatt_operation(
+    JsFuture::from(service.get_characteristic_with_str(BATTERY_LEVEL_CHARACTERISTIC_UUID)),
+    gatt_timeout_ms,
+  )
+  .await
+  .ok()?;
+  info!("Battery Service found on device {}", device.name().unwrap());
+  Some(chr.into())
+}
+
+// Attaches the notification callback and starts notifications for a single
+// endpoint. Shared between the initial Subscribe command handling and
+// subscription restoration after a reconnect.
+async fn subscribe_endpoint(
+  chr: BluetoothRemoteGattCharacteristic,
+  ep: Endpoint,
+  event_sender: broadcast::Sender<HardwareEvent>,
+  id: String,
+  gatt_timeout_ms: u32,
+) -> Result<(), ButtplugDeviceError> {
+  let onchange_callback = Closure::wrap(Box::new(move |e: MessageEvent| {
+    let event_chr: BluetoothRemoteGattCharacteristic =
+      BluetoothRemoteGattCharacteristic::from(JsValue::from(e.target().unwrap()));
+    let value = Uint8Array::new_with_byte_offset(
+      &JsValue::from(event_chr.value().unwrap().buffer()),
+      0,
+    );
+    let value_vec = value.to_vec();
+    debug!("Subscription notification from {}: {:?}", ep, value_vec);
+    event_sender
+      .send(HardwareEvent::Notification(id.clone(), ep, value_vec))
+      .unwrap();
+  }) as Box<dyn FnMut(MessageEvent)>);
+  chr.set_oncharacteristicvaluechanged(Some(onchange_callback.as_ref().unchecked_ref()));
+  onchange_callback.forget();
+  await_gatt_operation(JsFuture::from(chr.start_notifications()), gatt_timeout_ms).await?;
+  Ok(())
+}
+
+// Repeatedly retries the GATT connection with exponential backoff, restoring
+// every subscription that was active before the drop. A subscription that
+// fails to restore is dropped from the returned set rather than left marked
+// as subscribed, since no live notification is actually flowing for it.
+// Returns `None` once `max_attempts` is exhausted without a successful
+// reconnect.
+async fn reconnect_with_backoff(
+  device: &BluetoothDevice,
+  btle_protocol: &BluetoothLESpecifier,
+  blocklist: &GattBlocklist,
+  gatt_timeout_ms: u32,
+  max_attempts: u32,
+  event_sender: &broadcast::Sender<HardwareEvent>,
+  subscribed_endpoints: &HashSet<Endpoint>,
+) -> Option<(
+  HashMap<Endpoint, BluetoothRemoteGattCharacteristic>,
+  HashSet<Endpoint>,
+  HashSet<Endpoint>,
+)> {
+  let mut backoff_ms = RECONNECT_INITIAL_BACKOFF_MS;
+  for attempt in 1..=max_attempts {
+    info!(
+      "Waiting {}ms before GATT reconnect attempt {}/{}",
+      backoff_ms, attempt, max_attempts
+    );
+    TimeoutFuture::new(backoff_ms).await;
+    match discover_gatt(device, btle_protocol, blocklist, gatt_timeout_ms).await {
+      Ok((char_map, write_blocked_endpoints)) => {
+        info!("GATT reconnect attempt {} succeeded", attempt);
+        let mut restored_endpoints = subscribed_endpoints.clone();
+        for ep in subscribed_endpoints {
+          let restored = match char_map.get(ep) {
+            Some(chr) => subscribe_endpoint(
+              chr.clone(),
+              ep.clone(),
+              event_sender.clone(),
+              device.id(),
+              gatt_timeout_ms,
+            )
+            .await
+            .is_ok(),
+            None => false,
+          };
+          if !restored {
+            error!("Failed to restore subscription on {:?}, marking unsubscribed", ep);
+            restored_endpoints.remove(ep);
+          }
+        }
+        return Some((char_map, write_blocked_endpoints, restored_endpoints));
+      }
+      Err(err) => {
+        error!("GATT reconnect attempt {} failed: {:?}", attempt, err);
+        backoff_ms = (backoff_ms * 2).min(RECONNECT_MAX_BACKOFF_MS);
+      }
+    }
+  }
+  None
+}
+
+// Fails every command already queued in the channel rather than dropping it
+// silently. Called right before the loop exits on a permanent disconnect, so
+// a `Write`/`Read`/`Subscribe`/`ReadBattery` sitting in the channel at that
+// moment completes its caller's `ButtplugFuture` with an error instead of
+// hanging forever.
+fn fail_queued_commands(device_command_receiver: &mut mpsc::Receiver<WebBluetoothDeviceCommand>) {
+  while let Ok(msg) = device_command_receiver.try_recv() {
+    let disconnected = || ButtplugDeviceError::DeviceCommunicationError("Device disconnected".to_string());
+    match msg {
+      WebBluetoothDeviceCommand::Write(_, waker) => waker.set_reply(Err(disconnected())),
+      WebBluetoothDeviceCommand::Read(_, waker) => waker.set_reply(Err(disconnected())),
+      WebBluetoothDeviceCommand::Subscribe(_, waker) => waker.set_reply(Err(disconnected())),
+      WebBluetoothDeviceCommand::Unsubscribe(_, waker) => waker.set_reply(Err(disconnected())),
+      WebBluetoothDeviceCommand::ReadBattery(waker) => waker.set_reply(Err(disconnected())),
+    }
+  }
+}
+
+async fn run_webbluetooth_loop(
+  device: BluetoothDevice,
+  btle_protocol: BluetoothLESpecifier,
+  device_local_event_sender: mpsc::Sender<WebBluetoothEvent>,
+  device_external_event_sender: broadcast::Sender<HardwareEvent>,
+  mut device_command_receiver: mpsc::Receiver<WebBluetoothDeviceCommand>,
+  config: WebBluetoothConfig,
+) {
+  let gatt_timeout_ms = config.gatt_timeout_ms;
+  let blocklist = config.blocklist.clone();
+  let (mut char_map, mut write_blocked_endpoints) =
+    match discover_gatt(&device, &btle_protocol, &blocklist, gatt_timeout_ms).await {
+      Ok(result) => result,
+      Err(err) => {
+        error!("Could not connect to GATT server: {:?}", err);
+        device_local_event_sender
+          .send(WebBluetoothEvent::Disconnected)
+          .await
+          .unwrap();
+        return;
+      }
+    };
+  let mut subscribed_endpoints: HashSet<Endpoint> = HashSet::new();
+  if let Some(battery_chr) = char_map.get(&Endpoint::RxBLEBattery).cloned() {
+    subscribed_endpoints.insert(Endpoint::RxBLEBattery);
     let event_sender = device_external_event_sender.clone();
     let id = device.id().clone();
+    spawn_local(async move {
+      if let Err(err) =
+        subscribe_endpoint(battery_chr, Endpoint::RxBLEBattery, event_sender, id, gatt_timeout_ms).await
+      {
+        error!("Failed to subscribe to battery notifications: {:?}", err);
+      }
+    });
+  }
+  let (disconnect_sender, mut disconnect_receiver) = mpsc::channel::<()>(1);
+  {
     let ondisconnected_callback = Closure::wrap(Box::new(move |_: Event| {
       info!("device disconnected!");
-      event_sender
-        .send(HardwareEvent::Disconnected(id.clone()))
-        .unwrap();
+      let _ = disconnect_sender.try_send(());
     }) as Box<dyn FnMut(Event)>);
     device.set_ongattserverdisconnected(Some(ondisconnected_callback.as_ref().unchecked_ref()));
     ondisconnected_callback.forget();
@@ -254,78 +535,205 @@ async fn run_webbluetooth_loop(
   {
     error!("Failed to send Connected event");
   }
-  while let Some(msg) = device_command_receiver.recv().await {
-    match msg {
-      WebBluetoothDeviceCommand::Write(write_cmd, waker) => {
-        debug!("Writing to endpoint {:?}", write_cmd.endpoint());
-        let chr = char_map.get(&write_cmd.endpoint()).unwrap().clone();
-        spawn_local(async move {
-          let data = write_cmd.data().clone();
-          let uint8_array = Uint8Array::from(&data[..]);
-          let write_result = chr.write_value_with_u8_array(&uint8_array); // Returns Result<Promise, JsValue>
-          match write_result {
-            Ok(promise) => match JsFuture::from(promise).await {
-              Ok(_) => waker.set_reply(Ok(())),
-              Err(err) => waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(
-                format!("Failed to write value: {:?}", err),
-              ))),
-            },
-            Err(err) => waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(
-              format!("Failed to write value: {:?}", err),
-            ))),
+  loop {
+    select! {
+      msg = device_command_receiver.recv() => {
+        let Some(msg) = msg else {
+          break;
+        };
+        match msg {
+          WebBluetoothDeviceCommand::Write(write_cmd, waker) => {
+            debug!("Writing to endpoint {:?}", write_cmd.endpoint());
+            if write_blocked_endpoints.contains(&write_cmd.endpoint()) {
+              waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(format!(
+                "Endpoint {:?} is blocklisted for writes",
+                write_cmd.endpoint()
+              ))));
+              continue;
+            }
+            let chr = match char_map.get(&write_cmd.endpoint()) {
+              Some(chr) => chr.clone(),
+              None => {
+                waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(format!(
+                  "No characteristic mapped to endpoint {:?}",
+                  write_cmd.endpoint()
+                ))));
+                continue;
+              }
+            };
+            spawn_local(async move {
+              let data = write_cmd.data().clone();
+              let uint8_array = Uint8Array::from(&data[..]);
+              let write_result = chr.write_value_with_u8_array(&uint8_array); // Returns Result<Promise, JsValue>
+              match write_result {
+                Ok(promise) => {
+                  match await_gatt_operation(JsFuture::from(promise), gatt_timeout_ms).await {
+                    Ok(_) => waker.set_reply(Ok(())),
+                    Err(err) => waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(
+                      format!("Failed to write value: {:?}", err),
+                    ))),
+                  }
+                }
+                Err(err) => waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(
+                  format!("Failed to write value: {:?}", err),
+                ))),
+              }
+            });
           }
-        });
-      }
-      WebBluetoothDeviceCommand::Read(read_cmd, waker) => {
-        debug!("Reading from endpoint {:?}", read_cmd.endpoint());
-        let chr = char_map.get(&read_cmd.endpoint()).unwrap().clone();
-        spawn_local(async move {
-          let read_value = JsFuture::from(chr.read_value()).await.unwrap();
-          let data_view = DataView::try_from(read_value).unwrap();
-          let mut body = vec![0; data_view.byte_length() as usize];
-          Uint8Array::new(&data_view).copy_to(&mut body[..]);
-          let reading = HardwareReading::new(read_cmd.endpoint(), &body);
-          waker.set_reply(Ok(reading));
-        });
-      }
-      WebBluetoothDeviceCommand::Subscribe(subscribe_cmd, waker) => {
-        debug!("Subscribing to endpoint {:?}", subscribe_cmd.endpoint());
-        let chr = char_map.get(&subscribe_cmd.endpoint()).unwrap().clone();
-        let ep = subscribe_cmd.endpoint();
-        let event_sender = device_external_event_sender.clone();
-        let id = device.id().clone();
-        let onchange_callback = Closure::wrap(Box::new(move |e: MessageEvent| {
-          let event_chr: BluetoothRemoteGattCharacteristic =
-            BluetoothRemoteGattCharacteristic::from(JsValue::from(e.target().unwrap()));
-          let value = Uint8Array::new_with_byte_offset(
-            &JsValue::from(event_chr.value().unwrap().buffer()),
-            0,
-          );
-          let value_vec = value.to_vec();
-          debug!("Subscription notification from {}: {:?}", ep, value_vec);
-          event_sender
-            .send(HardwareEvent::Notification(id.clone(), ep, value_vec))
-            .unwrap();
-        }) as Box<dyn FnMut(MessageEvent)>);
-        chr.set_oncharacteristicvaluechanged(Some(onchange_callback.as_ref().unchecked_ref()));
-        onchange_callback.forget();
-        spawn_local(async move {
-          JsFuture::from(chr.start_notifications()).await.unwrap();
-          debug!("Endpoint subscribed");
-          waker.set_reply(Ok(()));
-        });
+          WebBluetoothDeviceCommand::Read(read_cmd, waker) => {
+            debug!("Reading from endpoint {:?}", read_cmd.endpoint());
+            let chr = match char_map.get(&read_cmd.endpoint()) {
+              Some(chr) => chr.clone(),
+              None => {
+                waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(format!(
+                  "No characteristic mapped to endpoint {:?}",
+                  read_cmd.endpoint()
+                ))));
+                continue;
+              }
+            };
+            spawn_local(async move {
+              let read_value = match await_gatt_operation(JsFuture::from(chr.read_value()), gatt_timeout_ms).await {
+                Ok(val) => val,
+                Err(err) => {
+                  waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(format!(
+                    "Failed to read value: {:?}",
+                    err
+                  ))));
+                  return;
+                }
+              };
+              let data_view = DataView::try_from(read_value).unwrap();
+              let mut body = vec![0; data_view.byte_length() as usize];
+              Uint8Array::new(&data_view).copy_to(&mut body[..]);
+              let reading = HardwareReading::new(read_cmd.endpoint(), &body);
+              waker.set_reply(Ok(reading));
+            });
+          }
+          WebBluetoothDeviceCommand::Subscribe(subscribe_cmd, waker) => {
+            debug!("Subscribing to endpoint {:?}", subscribe_cmd.endpoint());
+            let chr = match char_map.get(&subscribe_cmd.endpoint()) {
+              Some(chr) => chr.clone(),
+              None => {
+                waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(format!(
+                  "No characteristic mapped to endpoint {:?}",
+                  subscribe_cmd.endpoint()
+                ))));
+                continue;
+              }
+            };
+            // Defense-in-depth: fully-blocklisted characteristics are already
+            // skipped during discovery (initial and on every reconnect) and so
+            // never end up in `char_map`; this re-checks the characteristic's
+            // own UUID before subscribing all the same.
+            if let Ok(uuid) = Uuid::parse_str(&chr.uuid()) {
+              if blocklist.uuid_is_blocklisted(&uuid, GattAccess::Subscribe) {
+                waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(format!(
+                  "Endpoint {:?} is blocklisted for subscription",
+                  subscribe_cmd.endpoint()
+                ))));
+                continue;
+              }
+            }
+            let ep = subscribe_cmd.endpoint();
+            subscribed_endpoints.insert(ep.clone());
+            let event_sender = device_external_event_sender.clone();
+            let id = device.id().clone();
+            spawn_local(async move {
+              match subscribe_endpoint(chr, ep, event_sender, id, gatt_timeout_ms).await {
+                Ok(_) => {
+                  debug!("Endpoint subscribed");
+                  waker.set_reply(Ok(()));
+                }
+                Err(err) => waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(
+                  format!("Failed to subscribe: {:?}", err),
+                ))),
+              }
+            });
+          }
+          WebBluetoothDeviceCommand::ReadBattery(waker) => {
+            debug!("Reading battery level");
+            let chr = match char_map.get(&Endpoint::RxBLEBattery) {
+              Some(chr) => chr.clone(),
+              None => {
+                waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(
+                  "Device does not expose a Battery Level characteristic".to_string(),
+                )));
+                continue;
+              }
+            };
+            spawn_local(async move {
+              match await_gatt_operation(JsFuture::from(chr.read_value()), gatt_timeout_ms).await {
+                Ok(val) => {
+                  let data_view = DataView::try_from(val).unwrap();
+                  if data_view.byte_length() == 0 {
+                    waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(
+                      "Battery level read returned no data".to_string(),
+                    )));
+                  } else {
+                    waker.set_reply(Ok(data_view.get_uint8(0)));
+                  }
+                }
+                Err(err) => waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(
+                  format!("Failed to read battery level: {:?}", err),
+                ))),
+              }
+            });
+          }
+          WebBluetoothDeviceCommand::Unsubscribe(unsubscribe_cmd, waker) => {
+            debug!("Unsubscribing from endpoint {:?}", unsubscribe_cmd.endpoint());
+            subscribed_endpoints.remove(&unsubscribe_cmd.endpoint());
+            let chr = match char_map.get(&unsubscribe_cmd.endpoint()) {
+              Some(chr) => chr.clone(),
+              None => {
+                waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(format!(
+                  "No characteristic mapped to endpoint {:?}",
+                  unsubscribe_cmd.endpoint()
+                ))));
+                continue;
+              }
+            };
+            spawn_local(async move {
+              match await_gatt_operation(JsFuture::from(chr.stop_notifications()), gatt_timeout_ms).await {
+                Ok(_) => waker.set_reply(Ok(())),
+                Err(err) => waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(
+                  format!("Failed to unsubscribe: {:?}", err),
+                ))),
+              }
+            });
+          }
+        }
       }
-      WebBluetoothDeviceCommand::Unsubscribe(unsubscribe_cmd, waker) => {
-        debug!("Unsubscribing from endpoint {:?}", unsubscribe_cmd.endpoint());
-        let chr = char_map.get(&unsubscribe_cmd.endpoint()).unwrap().clone();
-        spawn_local(async move {
-          match JsFuture::from(chr.stop_notifications()).await {
-            Ok(_) => waker.set_reply(Ok(())),
-            Err(err) => waker.set_reply(Err(ButtplugDeviceError::DeviceCommunicationError(
-              format!("Failed to unsubscribe: {:?}", err),
-            ))),
+      _ = disconnect_receiver.recv() => {
+        if !config.reconnect {
+          fail_queued_commands(&mut device_command_receiver);
+          let _ = device_external_event_sender.send(HardwareEvent::Disconnected(device.id()));
+          break;
+        }
+        info!("GATT server disconnected, attempting to reconnect...");
+        match reconnect_with_backoff(
+          &device,
+          &btle_protocol,
+          &blocklist,
+          gatt_timeout_ms,
+          config.max_reconnect_attempts,
+          &device_external_event_sender,
+          &subscribed_endpoints,
+        )
+        .await
+        {
+          Some((new_char_map, new_write_blocked_endpoints, new_subscribed_endpoints)) => {
+            char_map = new_char_map;
+            write_blocked_endpoints = new_write_blocked_endpoints;
+            subscribed_endpoints = new_subscribed_endpoints;
+          }
+          None => {
+            error!("Reconnection abandoned after {} attempts", config.max_reconnect_attempts);
+            fail_queued_commands(&mut device_command_receiver);
+            let _ = device_external_event_sender.send(HardwareEvent::Disconnected(device.id()));
+            break;
           }
-        });
+        }
       }
     }
   }
@@ -349,6 +757,24 @@ impl WebBluetoothHardware {
       device_command_sender,
     }
   }
+
+  // Reads the standard Battery Service level, for devices where
+  // `discover_gatt` found a Battery Level characteristic.
+  pub fn read_battery(&self) -> BoxFuture<'static, Result<u8, ButtplugDeviceError>> {
+    let sender = self.device_command_sender.clone();
+    Box::pin(async move {
+      let fut = WebBluetoothBatteryResultFuture::default();
+      let waker = fut.get_state_clone();
+      if sender
+        .send(WebBluetoothDeviceCommand::ReadBattery(waker))
+        .await
+        .is_err()
+      {
+        error!("Failed to send ReadBattery command");
+      }
+      fut.await
+    })
+  }
 }
 
 impl HardwareInternal for WebBluetoothHardware {
@@ -1,4 +1,6 @@
-use super::webbluetooth_hardware::WebBluetoothHardwareConnector;
+use super::webbluetooth_hardware::{
+  WebBluetoothConfig, WebBluetoothHardwareConnector, DEFAULT_MAX_RECONNECT_ATTEMPTS,
+};
 use buttplug::{
   core::ButtplugResultFuture,
   server::device::{
@@ -11,24 +13,119 @@ use buttplug::{
   util::device_configuration::create_test_dcm,
 };
 use futures::future;
-use js_sys::Array;
+use js_sys::{Array, Uint8Array};
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::mpsc::Sender;
+use uuid::Uuid;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 use web_sys::BluetoothDevice;
 
-#[derive(Default)]
+// Which kind of GATT access is being attempted against a characteristic or
+// service, so the blocklist can distinguish "never touch" from "never write".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GattAccess {
+  Read,
+  Write,
+  Subscribe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlocklistExclusion {
+  All,
+  ExcludeWrites,
+}
+
+// A starter set of entries from the Web Bluetooth GATT blocklist
+// (https://github.com/WebBluetoothCG/registries/blob/main/gatt_blocklist.txt),
+// which browsers enforce against `optionalServices` and every GATT access so
+// a page can never touch security-sensitive endpoints. We talk to
+// `web_sys::Bluetooth` directly and so get none of that enforcement for
+// free; this is not the full registry, so extend it as more entries become
+// relevant to the protocols we support.
+pub struct GattBlocklist {
+  entries: HashMap<Uuid, BlocklistExclusion>,
+}
+
+impl GattBlocklist {
+  pub fn new() -> Self {
+    let mut entries = HashMap::new();
+    // Human Interface Device service: exclude entirely.
+    entries.insert(
+      Uuid::parse_str("00001812-0000-1000-8000-00805f9b34fb").unwrap(),
+      BlocklistExclusion::All,
+    );
+    // GAP Peripheral Privacy Flag characteristic: writes excluded.
+    entries.insert(
+      Uuid::parse_str("00002a02-0000-1000-8000-00805f9b34fb").unwrap(),
+      BlocklistExclusion::ExcludeWrites,
+    );
+    // Serial Number String characteristic: exclude entirely.
+    entries.insert(
+      Uuid::parse_str("00002a25-0000-1000-8000-00805f9b34fb").unwrap(),
+      BlocklistExclusion::All,
+    );
+    Self { entries }
+  }
+
+  pub fn uuid_is_blocklisted(&self, uuid: &Uuid, access: GattAccess) -> bool {
+    match self.entries.get(uuid) {
+      Some(BlocklistExclusion::All) => true,
+      Some(BlocklistExclusion::ExcludeWrites) => access == GattAccess::Write,
+      None => false,
+    }
+  }
+}
+
+impl Default for GattBlocklist {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 pub struct WebBluetoothCommunicationManagerBuilder {
+  blocklist: Arc<GattBlocklist>,
+  reconnect: bool,
+  max_reconnect_attempts: u32,
+}
+
+impl Default for WebBluetoothCommunicationManagerBuilder {
+  fn default() -> Self {
+    Self {
+      blocklist: Arc::new(GattBlocklist::new()),
+      reconnect: false,
+      max_reconnect_attempts: DEFAULT_MAX_RECONNECT_ATTEMPTS,
+    }
+  }
+}
+
+impl WebBluetoothCommunicationManagerBuilder {
+  // Enables automatic reconnection (with subscription restoration) after a
+  // `gattserverdisconnected` event, retrying up to `max_attempts` times with
+  // exponential backoff instead of immediately surfacing a disconnect.
+  pub fn reconnect(mut self, max_attempts: u32) -> Self {
+    self.reconnect = true;
+    self.max_reconnect_attempts = max_attempts;
+    self
+  }
 }
 
 impl HardwareCommunicationManagerBuilder for WebBluetoothCommunicationManagerBuilder {
   fn finish(&mut self, sender: Sender<HardwareCommunicationManagerEvent>) -> Box<dyn HardwareCommunicationManager> {
-    Box::new(WebBluetoothCommunicationManager { sender })
+    Box::new(WebBluetoothCommunicationManager {
+      sender,
+      blocklist: self.blocklist.clone(),
+      reconnect: self.reconnect,
+      max_reconnect_attempts: self.max_reconnect_attempts,
+    })
   }
 }
 
 pub struct WebBluetoothCommunicationManager {
   sender: Sender<HardwareCommunicationManagerEvent>,
+  blocklist: Arc<GattBlocklist>,
+  reconnect: bool,
+  max_reconnect_attempts: u32,
 }
 
 #[wasm_bindgen]
@@ -49,6 +146,9 @@ impl HardwareCommunicationManager for WebBluetoothCommunicationManager {
   fn start_scanning(&mut self) -> ButtplugResultFuture {
     info!("WebBluetooth manager scanning");
     let sender_clone = self.sender.clone();
+    let blocklist = self.blocklist.clone();
+    let reconnect = self.reconnect;
+    let max_reconnect_attempts = self.max_reconnect_attempts;
     spawn_local(async move {
       let nav = web_sys::window().unwrap().navigator();
       if nav.bluetooth().is_none() {
@@ -75,8 +175,42 @@ impl HardwareCommunicationManager for WebBluetoothCommunicationManager {
               filters.push(&filter.into());
             }
             for (service, _) in btle.services() {
+              if blocklist.uuid_is_blocklisted(service, GattAccess::Read) {
+                info!("Service {} is blocklisted, omitting from scan request", service);
+                continue;
+              }
               optional_services.push(&service.to_string().into());
             }
+            for manufacturer_data in btle.manufacturer_data() {
+              let manufacturer_filter =
+                web_sys::BluetoothManufacturerDataFilterInit::new(manufacturer_data.company_identifier());
+              if let Some(data_prefix) = manufacturer_data.data_prefix() {
+                manufacturer_filter.set_data_prefix(&Uint8Array::from(&data_prefix[..]));
+              }
+              if let Some(mask) = manufacturer_data.mask() {
+                manufacturer_filter.set_mask(&Uint8Array::from(&mask[..]));
+              }
+              let filter = web_sys::BluetoothLeScanFilterInit::new();
+              let manufacturer_data_array = Array::new();
+              manufacturer_data_array.push(&manufacturer_filter.into());
+              filter.set_manufacturer_data(&manufacturer_data_array.into());
+              filters.push(&filter.into());
+            }
+            for service_data in btle.service_data() {
+              let service_data_filter =
+                web_sys::BluetoothServiceDataFilterInit::new(&service_data.service().to_string());
+              if let Some(data_prefix) = service_data.data_prefix() {
+                service_data_filter.set_data_prefix(&Uint8Array::from(&data_prefix[..]));
+              }
+              if let Some(mask) = service_data.mask() {
+                service_data_filter.set_mask(&Uint8Array::from(&mask[..]));
+              }
+              let filter = web_sys::BluetoothLeScanFilterInit::new();
+              let service_data_array = Array::new();
+              service_data_array.push(&service_data_filter.into());
+              filter.set_service_data(&service_data_array.into());
+              filters.push(&filter.into());
+            }
           }
         }
       }
@@ -91,7 +225,15 @@ impl HardwareCommunicationManager for WebBluetoothCommunicationManager {
           }
           let name = bt_device.name().unwrap();
           let address = bt_device.id();
-          let device_creator = Box::new(WebBluetoothHardwareConnector::new(bt_device));
+          let config = WebBluetoothConfig {
+            blocklist: blocklist.clone(),
+            reconnect,
+            max_reconnect_attempts,
+            ..Default::default()
+          };
+          let device_creator = Box::new(WebBluetoothHardwareConnector::new_with_config(
+            bt_device, config,
+          ));
           if sender_clone
             .send(HardwareCommunicationManagerEvent::DeviceFound {
               name,